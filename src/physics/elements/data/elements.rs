@@ -1,3 +1,5 @@
+use anyhow::{anyhow, bail, Result};
+use crate::numerics::float::Float;
 use super::AtomicElement;
 
 
@@ -715,3 +717,132 @@ pub static ELEMENTS: [AtomicElement; 118] = [
         A: 294.213928,
     },
 ];
+
+
+//================================================================================================
+// Name-based lookups, mirroring the symbol / Z lookups.
+//================================================================================================
+
+impl AtomicElement {
+    /// Looks up an element by full name, case-insensitively (e.g. `"iron"`, `"Iron"`).
+    pub fn from_name(name: &str) -> Result<&'static Self> {
+        ELEMENTS
+            .iter()
+            .find(|element| element.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("bad element name (no match for '{}')", name))
+    }
+
+    /// Looks up an element by chemical symbol or full name, case-insensitively, trying the
+    /// symbol first (e.g. `"fe"`, `"Fe"` and `"Iron"` all resolve to iron).
+    pub fn from_str(token: &str) -> Result<&'static Self> {
+        Self::from_symbol(token)
+            .or_else(|_| Self::from_name(token))
+    }
+}
+
+
+//================================================================================================
+// Isotopic composition.
+//================================================================================================
+
+/// A single isotope, with its mass number, exact atomic mass and natural fractional abundance
+/// (in percent).
+#[derive(Clone, Copy, Debug)]
+pub struct Isotope {
+    pub mass_number: u32,
+    pub mass: Float,
+    pub abundance: Float,
+}
+
+impl Isotope {
+    /// Parses a `"<A>:<mass>:<abundance>;..."`-encoded isotopic composition, as used by the
+    /// constants library (e.g. `"107:106.905093:51.839;109:108.904756:48.161"` for silver).
+    pub fn parse_table(encoding: &str) -> Result<Vec<Self>> {
+        let mut isotopes = Vec::new();
+        for entry in encoding.split(';') {
+            let mut fields = entry.split(':');
+            let mass_number: u32 = fields
+                .next()
+                .ok_or_else(|| anyhow!("bad isotope entry '{}' (missing mass number)", entry))?
+                .parse()
+                .map_err(|_| anyhow!("bad mass number in isotope entry '{}'", entry))?;
+            let mass: Float = fields
+                .next()
+                .ok_or_else(|| anyhow!("bad isotope entry '{}' (missing mass)", entry))?
+                .parse()
+                .map_err(|_| anyhow!("bad mass in isotope entry '{}'", entry))?;
+            let abundance: Float = fields
+                .next()
+                .ok_or_else(|| anyhow!("bad isotope entry '{}' (missing abundance)", entry))?
+                .parse()
+                .map_err(|_| anyhow!("bad abundance in isotope entry '{}'", entry))?;
+            isotopes.push(Self { mass_number, mass, abundance });
+        }
+        if isotopes.is_empty() {
+            bail!("empty isotopic composition");
+        }
+        Ok(isotopes)
+    }
+
+    /// Returns the abundance-weighted mean mass of a set of isotopes, renormalizing the
+    /// abundances to sum to one. Rejects an empty or zero-abundance composition.
+    pub fn mean_mass(isotopes: &[Self]) -> Result<Float> {
+        let total: Float = isotopes.iter().map(|isotope| isotope.abundance).sum();
+        if total <= 0.0 {
+            bail!("empty or zero-abundance isotopic composition");
+        }
+        let mean = isotopes
+            .iter()
+            .map(|isotope| isotope.mass * isotope.abundance)
+            .sum::<Float>() / total;
+        Ok(mean)
+    }
+}
+
+
+//================================================================================================
+// Per-element isotopic composition, keyed by chemical symbol.
+//================================================================================================
+
+/// Natural isotopic composition, `"<A>:<mass>:<abundance>;..."`-encoded (see
+/// [`Isotope::parse_table`]), for a subset of elements commonly used in radiation-transport
+/// materials. Coverage is partial: elements absent from this table have no tabulated composition,
+/// and [`AtomicElement::isotopes`] returns `None` for them rather than an empty result.
+static ISOTOPE_TABLES: [(&str, &str); 13] = [
+    ("H", "1:1.00782503207:99.9885;2:2.0141017778:0.0115"),
+    ("C", "12:12.0:98.93;13:13.0033548378:1.07"),
+    ("N", "14:14.0030740048:99.636;15:15.0001088982:0.364"),
+    ("O", "16:15.99491461956:99.757;17:16.9991317:0.038;18:17.9991610:0.205"),
+    ("Na", "23:22.9897692809:100.0"),
+    ("Al", "27:26.98153863:100.0"),
+    ("Si", "28:27.97692653246:92.223;29:28.9764947:4.685;30:29.973770171:3.092"),
+    ("Cl", "35:34.96885268:75.76;37:36.96590259:24.24"),
+    ("Ca", "40:39.96259098:96.941;42:41.95861801:0.647;43:42.9587666:0.135;44:43.9554818:2.086;\
+            46:45.9536926:0.004;48:47.952534:0.187"),
+    ("Fe", "54:53.9396105:5.845;56:55.9349375:91.754;57:56.9353940:2.119;58:57.9332756:0.282"),
+    ("Cu", "63:62.9295975:69.15;65:64.9277895:30.85"),
+    ("Ag", "107:106.905093:51.839;109:108.904756:48.161"),
+    ("Pb", "204:203.9730436:1.4;206:205.9744653:24.1;207:206.9758969:22.1;208:207.9766521:52.4"),
+];
+
+impl AtomicElement {
+    /// Returns this element's tabulated natural isotopic composition, if any (see
+    /// [`ISOTOPE_TABLES`] for coverage).
+    pub fn isotopes(&self) -> Option<Vec<Isotope>> {
+        ISOTOPE_TABLES
+            .iter()
+            .find(|(symbol, _)| *symbol == self.symbol)
+            .map(|(_, encoding)| {
+                Isotope::parse_table(encoding).expect("static isotope table is well-formed")
+            })
+    }
+
+    /// Returns this element's abundance-weighted mean isotopic mass, if tabulated. This is the
+    /// natural-abundance counterpart to the literature `A` hard-coded in [`ELEMENTS`] above,
+    /// derived from isotopic data rather than looked up.
+    pub fn mean_mass(&self) -> Option<Float> {
+        self.isotopes().map(|isotopes| {
+            Isotope::mean_mass(&isotopes).expect("static isotope table has positive abundances")
+        })
+    }
+}
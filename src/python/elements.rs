@@ -1,6 +1,7 @@
 use anyhow::Result;
 use crate::numerics::float::Float;
 use crate::physics::elements::AtomicElement;
+use crate::physics::elements::data::elements::Isotope;
 use pyo3::prelude::*;
 use pyo3::class::basic::CompareOp;
 use pyo3::exceptions::PyNotImplementedError;
@@ -67,6 +68,25 @@ impl PyAtomicElement {
         self.0.Z
     }
 
+    /// This element's tabulated natural isotopic composition, as a list of
+    /// `(mass_number, mass, abundance)` triplets, or `None` if not tabulated.
+    #[getter]
+    fn get_isotopes(&self) -> Option<Vec<(u32, Float, Float)>> {
+        self.0.isotopes().map(|isotopes| {
+            isotopes
+                .iter()
+                .map(|isotope| (isotope.mass_number, isotope.mass, isotope.abundance))
+                .collect()
+        })
+    }
+
+    /// This element's abundance-weighted mean isotopic mass, or `None` if its isotopic
+    /// composition is not tabulated.
+    #[getter]
+    fn get_mean_mass(&self) -> Option<Float> {
+        self.0.mean_mass()
+    }
+
     fn __getstate__<'py>(&self, py: Python<'py>) -> Result<&'py PyBytes> {
         let mut buffer = Vec::new();
         self.0.serialize(&mut Serializer::new(&mut buffer))?;
@@ -131,3 +151,20 @@ pub fn elements(py: Python, args: &PyTuple) -> Result<PyObject> {
     };
     Ok(result)
 }
+
+/// Looks up an element by chemical symbol or name (e.g. `"Fe"` or `"Iron"`), case-insensitively.
+#[pyfunction]
+pub fn periodic_table(token: &str) -> Result<PyAtomicElement> {
+    Ok(PyAtomicElement(AtomicElement::from_str(token)?))
+}
+
+/// Computes the abundance-weighted mean atomic mass of an arbitrary, caller-supplied
+/// `"<A>:<mass>:<abundance>;..."`-encoded isotopic composition (e.g.
+/// `"107:106.905093:51.839;109:108.904756:48.161"` for silver) -- e.g. for an element, or an
+/// isotope blend, not covered by `AtomicElement`'s own tabulated composition
+/// (`AtomicElement.isotopes`/`AtomicElement.mean_mass`).
+#[pyfunction]
+pub fn isotopic_mean_mass(encoding: &str) -> Result<Float> {
+    let isotopes = Isotope::parse_table(encoding)?;
+    Isotope::mean_mass(&isotopes)
+}
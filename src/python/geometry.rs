@@ -1,14 +1,19 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use crate::numerics::Float;
 use crate::transport::{
     density::DensityModel,
-    geometry::{ExternalGeometry, ExternalTracer, GeometryDefinition, GeometryTracer,
-               SimpleGeometry, StratifiedGeometry, TopographyMap},
+    geometry::{ElevationDtype, ExternalGeometry, ExternalTracer, GeometryDefinition,
+               GeometryTracer, SimpleGeometry, StratifiedGeometry, TopographyMap},
     PhotonState,
 };
 use pyo3::prelude::*;
 use pyo3::types::PyTuple;
+use rayon::prelude::*;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 use super::ctrlc_catched;
 use super::macros::value_error;
 use super::materials::PyMaterialDefinition;
@@ -16,6 +21,66 @@ use super::numpy::{ArrayOrFloat, PyArray, PyArrayFlags};
 use super::transport::CState;
 
 
+// ===============================================================================================
+// Background Ctrl+C watcher, for GIL-released parallel loops.
+// ===============================================================================================
+
+/// Periodically re-acquires the GIL, from a dedicated thread, to poll for a Ctrl+C interrupt
+/// caught by Python while the main thread runs compute with the GIL released. Workers check
+/// `interrupted()`, which only touches an atomic flag and never needs the GIL. The original
+/// `PyErr` raised by `ctrlc_catched()` (typically a `KeyboardInterrupt`) is kept around so that
+/// it can be propagated as-is, via `take_error()`, instead of being replaced by a generic error.
+struct InterruptWatcher {
+    stop: Arc<AtomicBool>,
+    flag: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<PyErr>>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl InterruptWatcher {
+    fn spawn() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::new(AtomicBool::new(false));
+        let error = Arc::new(Mutex::new(None));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            let flag = Arc::clone(&flag);
+            let error = Arc::clone(&error);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Err(err) = ctrlc_catched() {
+                        *error.lock().unwrap() = Some(err);
+                        flag.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                    thread::sleep(Duration::from_millis(50));
+                }
+            })
+        };
+        Self { stop, flag, error, handle: Some(handle) }
+    }
+
+    #[inline]
+    fn interrupted(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    /// Takes the original `PyErr` caught by the watcher thread, if any.
+    fn take_error(&self) -> Option<PyErr> {
+        self.error.lock().unwrap().take()
+    }
+}
+
+impl Drop for InterruptWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+
 // ===============================================================================================
 // Python wrapper for a description of a geometry sector.
 // ===============================================================================================
@@ -157,19 +222,42 @@ impl PyExternalGeometry {
 
     fn locate(&self, states: &PyArray<CState>) -> Result<PyObject> {
         let py = states.py();
+        let n = states.size();
         let sectors = PyArray::<usize>::empty(py, &states.shape())?;
-        let mut tracer = ExternalTracer::new(&self.inner)?;
         let m = self.inner.sectors().len();
-        let n = states.size();
-        for i in 0..n {
-            let state: PhotonState = states.get(i)?.into();
-            tracer.reset(state.position, state.direction)?;
-            let sector = tracer.sector().unwrap_or(m);
-            sectors.set(i, sector)?;
 
-            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
-                ctrlc_catched()?;
-            }
+        // Read the input states and allocate the output buffer up front, then release the GIL
+        // for the compute-heavy region below.
+        let input: Vec<CState> = (0..n)
+            .map(|i| states.get(i))
+            .collect::<PyResult<_>>()?;
+        let mut output = vec![0_usize; n];
+
+        let watcher = InterruptWatcher::spawn();
+        let result = py.allow_threads(|| -> Result<()> {
+            input
+                .par_iter()
+                .zip(output.par_iter_mut())
+                .try_for_each(|(state, sector)| -> Result<()> {
+                    if watcher.interrupted() {
+                        bail!("interrupted (Ctrl+C)")
+                    }
+                    let state: PhotonState = (*state).into();
+                    let mut tracer = ExternalTracer::new(&self.inner)?;
+                    tracer.reset(state.position, state.direction)?;
+                    *sector = tracer.sector().unwrap_or(m);
+                    Ok(())
+                })
+        });
+        let interrupt = watcher.take_error();
+        drop(watcher);
+        if let Some(err) = interrupt {
+            return Err(err.into());
+        }
+        result?;
+
+        for (i, sector) in output.into_iter().enumerate() {
+            sectors.set(i, sector)?;
         }
         let sectors: &PyAny = sectors;
         Ok(sectors.into_py(py))
@@ -201,50 +289,71 @@ impl PyExternalGeometry {
         let result = PyArray::<Float>::empty(py, &shape)?;
 
         let density = density.unwrap_or(false);
-        let mut tracer = ExternalTracer::new(&self.inner)?;
-        let mut k: usize = 0;
-        for i in 0..n {
-            let state: PhotonState = states.get(i)?.into();
-            let mut grammages: Vec<Float> = vec![0.0; m];
-            tracer.reset(state.position, state.direction)?;
-            let mut length = match lengths.as_ref() {
-                None => Float::INFINITY,
-                Some(lengths) => match &lengths {
-                    ArrayOrFloat::Array(lengths) => lengths.get(i)?,
-                    ArrayOrFloat::Float(lengths) => *lengths,
-                },
-            };
-            loop {
-                match tracer.sector() {
-                    None => break,
-                    Some(sector) => {
-                        let step_length = tracer.trace(length)?;
-                        if density {
-                            let model = &self.inner.sectors[sector].density;
-                            let position = tracer.position();
-                            grammages[sector] += model.column_depth(
-                                position, state.direction, step_length
-                            );
-                        } else {
-                            grammages[sector] += step_length;
-                        }
-                        if lengths.is_some() {
-                            length -= step_length;
-                            if length <= 0.0 { break }
-                        }
-                        tracer.update(step_length, state.direction)?;
+        let bounded = lengths.is_some();
+
+        // Read the input states and lengths, and allocate the output buffer up front, then
+        // release the GIL for the compute-heavy region below.
+        let input: Vec<(PhotonState, Float)> = (0..n)
+            .map(|i| -> Result<(PhotonState, Float)> {
+                let state: PhotonState = states.get(i)?.into();
+                let length = match lengths.as_ref() {
+                    None => Float::INFINITY,
+                    Some(lengths) => match &lengths {
+                        ArrayOrFloat::Array(lengths) => lengths.get(i)?,
+                        ArrayOrFloat::Float(lengths) => *lengths,
                     },
-                }
-                k += 1;
-                if k == 1000 { // Check for a Ctrl+C interrupt, catched by Python.
-                    ctrlc_catched()?;
-                    k = 0;
-                }
-            }
-            let j0 = i * m;
-            for j in 0..m {
-                result.set(j0 + j, grammages[j])?;
-            }
+                };
+                Ok((state, length))
+            })
+            .collect::<Result<_>>()?;
+        let mut output = vec![0.0; n * m];
+
+        let watcher = InterruptWatcher::spawn();
+        let trace_result = py.allow_threads(|| -> Result<()> {
+            input
+                .par_iter()
+                .zip(output.par_chunks_mut(m))
+                .try_for_each(|((state, length), grammages)| -> Result<()> {
+                    if watcher.interrupted() {
+                        bail!("interrupted (Ctrl+C)")
+                    }
+                    let mut length = *length;
+                    let mut tracer = ExternalTracer::new(&self.inner)?;
+                    tracer.reset(state.position, state.direction)?;
+                    loop {
+                        match tracer.sector() {
+                            None => break,
+                            Some(sector) => {
+                                let step_length = tracer.trace(length)?;
+                                if density {
+                                    let model = &self.inner.sectors[sector].density;
+                                    let position = tracer.position();
+                                    grammages[sector] += model.column_depth(
+                                        position, state.direction, step_length
+                                    );
+                                } else {
+                                    grammages[sector] += step_length;
+                                }
+                                if bounded {
+                                    length -= step_length;
+                                    if length <= 0.0 { break }
+                                }
+                                tracer.update(step_length, state.direction)?;
+                            },
+                        }
+                    }
+                    Ok(())
+                })
+        });
+        let interrupt = watcher.take_error();
+        drop(watcher);
+        if let Some(err) = interrupt {
+            return Err(err.into());
+        }
+        trace_result?;
+
+        for (k, value) in output.into_iter().enumerate() {
+            result.set(k, value)?;
         }
         let result: &PyAny = result;
         Ok(result.into_py(py))
@@ -352,43 +461,31 @@ impl PyTopographyMap {
             },
         };
 
-        let range = |min, max, n| -> Result<PyObject> {
-            let array = PyArray::<Float>::empty(py, &[n])?;
-            array.set(0, min)?;
-            let delta = (max - min) / ((n - 1) as Float);
-            for i in 1..(n-1) {
-                let v = delta * (i as Float) + min;
-                array.set(i, v)?;
-            }
-            array.set(n - 1, max)?;
-            array.readonly();
-            Ok(array.into_py(py))
-        };
-
         // Build map.
         let mut map = TopographyMap::new(
             xrange[0], xrange[1], shape[1], yrange[0], yrange[1], shape[0]
         );
         if let Some(z) = z {
+            let dense = map.storage.as_dense_mut().unwrap();
             for i in 0..shape[0] {
                 for j in 0..shape[1] {
                     let k = i * shape[1] + j;
-                    map.z[(i, j)] = z.get(k)?;
+                    dense[(i, j)] = z.get(k)?;
                 }
             }
         }
 
         // Build typed Py-object.
         let inner = Rc::new(map);
-        let x = range(xrange[0], xrange[1], shape[1])?;
-        let y = range(yrange[0], yrange[1], shape[0])?;
+        let x = Self::linspace_array(py, xrange[0], xrange[1], shape[1])?;
+        let y = Self::linspace_array(py, yrange[0], yrange[1], shape[0])?;
         let z = py.None();
         let result = Py::new(py, Self { inner, x, y, z })?;
 
         // Create view of z-data, linked to Py-object.
         let z: &PyAny = PyArray::from_data(
             py,
-            result.borrow(py).inner.z.as_ref(),
+            result.borrow(py).inner.storage.as_dense().unwrap().as_ref(),
             result.as_ref(py),
             PyArrayFlags::ReadWrite,
             Some(&shape),
@@ -399,8 +496,159 @@ impl PyTopographyMap {
         Ok(result)
     }
 
-    fn __call__(&self, x: Float, y: Float) -> Option<Float> { // XXX vectorise and fill
-        self.inner.z(x, y)
+    /// Opens a memory-mapped, out-of-core topography map from a raw binary elevation grid.
+    ///
+    /// Unlike the regular constructor, the `z` grid is not copied into memory: it is mmap'd
+    /// read-only from `path` and resolved lazily, making continental-scale DEMs usable with a
+    /// small resident footprint.
+    #[staticmethod]
+    fn open(
+        py: Python,
+        path: &str,
+        xrange: [Float; 2],
+        yrange: [Float; 2],
+        shape: [usize; 2],
+        dtype: Option<&str>,
+    ) -> Result<Py<Self>> {
+        let dtype = ElevationDtype::try_from(dtype.unwrap_or("f8"))?;
+        let map = TopographyMap::open(
+            path,
+            xrange[0], xrange[1], shape[1],
+            yrange[0], yrange[1], shape[0],
+            dtype,
+        )?;
+
+        let inner = Rc::new(map);
+        let x = Self::linspace_array(py, xrange[0], xrange[1], shape[1])?;
+        let y = Self::linspace_array(py, yrange[0], yrange[1], shape[0])?;
+        // The z-grid is memory-mapped, not resident: it is not exposed as a NumPy view.
+        let z = py.None();
+        let result = Py::new(py, Self { inner, x, y, z })?;
+
+        Ok(result)
+    }
+
+    /// Loads a topography map from an ESRI ASCII grid (`.asc`) DEM file.
+    ///
+    /// The grid's geotransform (origin, cell size and shape) is read from the file's header, and
+    /// its `NODATA_value` sentinel, if any, is mapped to `nan`.
+    #[staticmethod]
+    fn from_asc(py: Python, path: &str) -> Result<Py<Self>> {
+        let map = TopographyMap::from_asc(path)?;
+        Self::from_dense(py, map)
+    }
+
+    /// Loads a single band of a GeoTIFF DEM file.
+    ///
+    /// The grid's geotransform is read from the file's GeoTIFF tags, and its `GDAL_NODATA`
+    /// sentinel, if any, is mapped to `nan`. `band` is 1-indexed.
+    #[staticmethod]
+    #[pyo3(signature = (path, band=1))]
+    fn from_geotiff(py: Python, path: &str, band: usize) -> Result<Py<Self>> {
+        let map = TopographyMap::from_geotiff(path, band)?;
+        Self::from_dense(py, map)
+    }
+
+    /// Evaluates the map's elevation at `(x, y)`, returning `fill` (`nan` by default) wherever
+    /// the point falls outside the map's domain.
+    ///
+    /// `x` and `y` must either share the same shape, or either one may be a bare scalar broadcast
+    /// against the other's shape: this is not full NumPy broadcasting (e.g. two differently
+    /// shaped arrays are rejected). A scalar `x` and `y` return a bare `float`, matching every
+    /// other scalar-in/scalar-out method in this API; otherwise a NumPy array is returned.
+    fn __call__(
+        &self,
+        py: Python,
+        x: ArrayOrFloat,
+        y: ArrayOrFloat,
+        fill: Option<Float>,
+    ) -> Result<PyObject> {
+        let fill = fill.unwrap_or(Float::NAN);
+
+        // x & y must share a shape, or either one may be a scalar broadcast against the other.
+        let shape = match (&x, &y) {
+            (ArrayOrFloat::Array(x), ArrayOrFloat::Array(y)) => {
+                if x.shape() != y.shape() {
+                    value_error!(
+                        "bad y (expected a shape {:?} array, found a shape {:?} array)",
+                        x.shape(),
+                        y.shape(),
+                    )
+                }
+                Some(x.shape())
+            },
+            (ArrayOrFloat::Array(x), ArrayOrFloat::Float(_)) => Some(x.shape()),
+            (ArrayOrFloat::Float(_), ArrayOrFloat::Array(y)) => Some(y.shape()),
+            (ArrayOrFloat::Float(_), ArrayOrFloat::Float(_)) => None,
+        };
+        let shape = match shape {
+            Some(shape) => shape,
+            None => {
+                // Both x and y are plain scalars: return a bare float instead of a shape-[1]
+                // array, for consistency with vectorized_result()'s scalar-in/scalar-out
+                // convention.
+                let zi = self.inner.z(x.get(0)?, y.get(0)?).unwrap_or(fill);
+                return Ok(zi.into_py(py));
+            },
+        };
+
+        let result = PyArray::<Float>::empty(py, &shape)?;
+        let n = result.size();
+        for i in 0..n {
+            let xi = x.get(i % x.size())?;
+            let yi = y.get(i % y.size())?;
+            let zi = self.inner.z(xi, yi).unwrap_or(fill);
+            result.set(i, zi)?;
+
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        let result: &PyAny = result;
+        Ok(result.into_py(py))
+    }
+}
+
+// Private interface.
+impl PyTopographyMap {
+    // Builds a linearly spaced, read-only array of `n` values from `min` to `max` (inclusive),
+    // used for the `x` and `y` axes of a map.
+    fn linspace_array(py: Python, min: Float, max: Float, n: usize) -> Result<PyObject> {
+        let array = PyArray::<Float>::empty(py, &[n])?;
+        array.set(0, min)?;
+        let delta = (max - min) / ((n - 1) as Float);
+        for i in 1..(n - 1) {
+            let v = delta * (i as Float) + min;
+            array.set(i, v)?;
+        }
+        array.set(n - 1, max)?;
+        array.readonly();
+        Ok(array.into_py(py))
+    }
+
+    // Wraps a dense `TopographyMap` (e.g. freshly parsed from a DEM file) into a typed Py-object,
+    // exposing its `x`, `y` and `z` grids as NumPy views.
+    fn from_dense(py: Python, map: TopographyMap) -> Result<Py<Self>> {
+        let (xmin, xmax, ymin, ymax) = map.bounds();
+        let (ny, nx) = map.shape();
+
+        let inner = Rc::new(map);
+        let x = Self::linspace_array(py, xmin, xmax, nx)?;
+        let y = Self::linspace_array(py, ymin, ymax, ny)?;
+        let z = py.None();
+        let result = Py::new(py, Self { inner, x, y, z })?;
+
+        let z: &PyAny = PyArray::from_data(
+            py,
+            result.borrow(py).inner.storage.as_dense().unwrap().as_ref(),
+            result.as_ref(py),
+            PyArrayFlags::ReadWrite,
+            Some(&[ny, nx]),
+        )?;
+        let z: PyObject = z.into();
+        result.borrow_mut(py).z = z;
+
+        Ok(result)
     }
 }
 
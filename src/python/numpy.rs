@@ -7,10 +7,14 @@ use pyo3::sync::GILOnceCell;
 use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
 use pyo3::types::PyCapsule;
 // Standard library.
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::ffi::{c_char, c_int, c_uchar, c_void};
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::sync::Mutex;
 // Local Python interface.
+use super::macros::value_error;
 use super::states::CState;
 use super::transport::CVertex;
 
@@ -294,53 +298,103 @@ impl PyUntypedArray {
 // Private interface.
 impl PyUntypedArray {
     pub fn data(&self, index: usize) -> PyResult<*mut c_char> {
-        let size = self.size();
-        if index >= size {
-            Err(PyIndexError::new_err(format!(
-                "ndarray index out of range (expected an index in [0, {}), found {})",
-                size,
-                index
-            )))
-        } else {
-            let offset = self.offset_of(index);
-            let obj: &PyArrayObject = self.as_ref();
-            let data = unsafe { obj.data.offset(offset as isize) };
-            Ok(data)
-        }
+        raw_data(self.as_ref(), index)
+    }
+
+    pub fn data_nd(&self, index: &[usize]) -> PyResult<*mut c_char> {
+        raw_data_nd(self.as_ref(), index)
     }
 
     fn offset_of(&self, index: usize) -> isize {
-        let shape = self.shape_slice();
-        let strides = self.strides_slice();
-        let n = shape.len();
-        if n == 0 {
-            0
-        } else {
-            let mut remainder = index;
-            let mut offset = 0_isize;
-            for i in (0..n).rev() {
-                let m = shape[i] as usize;
-                let j = remainder % m;
-                remainder = (remainder - j) / m;
-                offset += (j as isize) * strides[i];
-            }
-            offset
-        }
+        raw_offset_of(self.as_ref(), index)
     }
 
     #[inline]
     fn shape_slice(&self) -> &[npy_intp] {
-        let obj: &PyArrayObject = self.as_ref();
-        unsafe { std::slice::from_raw_parts(obj.dimensions, obj.nd as usize) }
+        raw_shape(self.as_ref())
     }
 
     #[inline]
     fn strides_slice(&self) -> &[npy_intp] {
-        let obj: &PyArrayObject = self.as_ref();
-        unsafe { std::slice::from_raw_parts(obj.strides, obj.nd as usize) }
+        raw_strides(self.as_ref())
+    }
+}
+
+// Free functions shared by the gil-ref interface above and the `Bound`-native one below, so that
+// both can walk an array's layout from a `&PyArrayObject` without going through the other.
+#[inline]
+fn raw_shape(obj: &PyArrayObject) -> &[npy_intp] {
+    unsafe { std::slice::from_raw_parts(obj.dimensions, obj.nd as usize) }
+}
+
+#[inline]
+fn raw_strides(obj: &PyArrayObject) -> &[npy_intp] {
+    unsafe { std::slice::from_raw_parts(obj.strides, obj.nd as usize) }
+}
+
+fn raw_size(obj: &PyArrayObject) -> usize {
+    raw_shape(obj).iter().product::<npy_intp>() as usize
+}
+
+fn raw_offset_of(obj: &PyArrayObject, index: usize) -> isize {
+    let shape = raw_shape(obj);
+    let strides = raw_strides(obj);
+    let n = shape.len();
+    if n == 0 {
+        0
+    } else {
+        let mut remainder = index;
+        let mut offset = 0_isize;
+        for i in (0..n).rev() {
+            let m = shape[i] as usize;
+            let j = remainder % m;
+            remainder = (remainder - j) / m;
+            offset += (j as isize) * strides[i];
+        }
+        offset
     }
 }
 
+fn raw_data(obj: &PyArrayObject, index: usize) -> PyResult<*mut c_char> {
+    let size = raw_size(obj);
+    if index >= size {
+        Err(PyIndexError::new_err(format!(
+            "ndarray index out of range (expected an index in [0, {}), found {})",
+            size,
+            index
+        )))
+    } else {
+        let offset = raw_offset_of(obj, index);
+        Ok(unsafe { obj.data.offset(offset) })
+    }
+}
+
+fn raw_data_nd(obj: &PyArrayObject, index: &[usize]) -> PyResult<*mut c_char> {
+    let shape = raw_shape(obj);
+    let strides = raw_strides(obj);
+    if index.len() != shape.len() {
+        return Err(PyIndexError::new_err(format!(
+            "bad ndarray index (expected {} indices, found {})",
+            shape.len(),
+            index.len(),
+        )))
+    }
+    let mut offset = 0_isize;
+    for (k, &i) in index.iter().enumerate() {
+        let m = shape[k] as usize;
+        if i >= m {
+            return Err(PyIndexError::new_err(format!(
+                "ndarray index out of range on axis {} (expected an index in [0, {}), found {})",
+                k,
+                m,
+                i,
+            )))
+        }
+        offset += (i as isize) * strides[k];
+    }
+    Ok(unsafe { obj.data.offset(offset) })
+}
+
 // Trait implementations.
 impl AsRef<PyArrayObject> for PyUntypedArray {
     #[inline]
@@ -385,6 +439,14 @@ where
     }
 
     pub fn empty<'py>(py: Python<'py>, shape: &[usize]) -> PyResult<Bound<'py, Self>> {
+        Self::empty_order(py, shape, Order::C)
+    }
+
+    pub fn empty_order<'py>(
+        py: Python<'py>,
+        shape: &[usize],
+        order: Order,
+    ) -> PyResult<Bound<'py, Self>> {
         let api = api(py);
         let empty = unsafe { *api.empty };
         let dtype = T::dtype(py)?;
@@ -393,7 +455,7 @@ where
             ndim,
             shape.as_ptr() as *const npy_intp,
             dtype.as_ptr(),
-            0,
+            order.into(),
         );
         if PyErr::occurred(py) {
             match PyErr::take(py) {
@@ -413,6 +475,17 @@ where
         base: &Bound<PyAny>,
         flags: PyArrayFlags,
         shape: Option<&[usize]>,
+    ) -> PyResult<Bound<'py, Self>> {
+        Self::from_data_order(py, data, base, flags, shape, Order::C)
+    }
+
+    pub fn from_data_order<'py>(
+        py: Python<'py>,
+        data: &[T],
+        base: &Bound<PyAny>,
+        flags: PyArrayFlags,
+        shape: Option<&[usize]>,
+        order: Order,
     ) -> PyResult<Bound<'py, Self>> {
         let api = api(py);
         let new_from_descriptor = unsafe { *api.new_from_descriptor };
@@ -434,14 +507,28 @@ where
                 Self::try_shape(shape)?
             },
         };
+        let strides = match order {
+            Order::C => None,
+            Order::Fortran => Some(Self::fortran_strides(&shape)),
+        };
+        let strides_ptr = strides.as_ref()
+            .map_or(std::ptr::null(), |s| s.as_ptr());
+        let contiguous_flag = match order {
+            Order::C => PyArrayFlags::C_CONTIGUOUS,
+            Order::Fortran => PyArrayFlags::F_CONTIGUOUS,
+        };
+        let writeable_flag = match flags {
+            PyArrayFlags::ReadOnly => 0,
+            PyArrayFlags::ReadWrite => PyArrayFlags::WRITEABLE,
+        };
         let array = new_from_descriptor(
             api.type_ndarray.as_ptr(),
             dtype.as_ptr(),
             ndim,
             shape.as_ptr() as *const npy_intp,
-            std::ptr::null_mut(),
+            strides_ptr,
             data.as_ptr() as *mut c_void,
-            flags.into(),
+            contiguous_flag | writeable_flag,
             std::ptr::null_mut(),
         );
         if PyErr::occurred(py) {
@@ -482,6 +569,37 @@ where
         Ok(value)
     }
 
+    pub fn get_nd(&self, index: &[usize]) -> PyResult<T> {
+        let data = self.data_nd(index)?;
+        let value = unsafe { *(data as *const T) };
+        Ok(value)
+    }
+
+    pub fn set_nd(&self, index: &[usize], value: T) -> PyResult<()> {
+        self.is_writeable()?;
+        let data = self.data_nd(index)?;
+        let element = unsafe { &mut *(data as *mut T) };
+        *element = value;
+        Ok(())
+    }
+
+    /// Iterates over array elements following arbitrary (e.g. strided or transposed) layouts.
+    pub fn iter(&self) -> PyArrayIter<T> {
+        PyArrayIter::new(self)
+    }
+
+    /// Iterates mutably over array elements following arbitrary (e.g. strided or transposed)
+    /// layouts.
+    ///
+    /// # Safety
+    ///
+    /// As with [`PyArray::slice_mut`], the returned references alias the underlying Python
+    /// object's memory without borrow-checking against other views of it.
+    pub unsafe fn iter_mut(&self) -> PyResult<PyArrayIterMut<T>> {
+        self.is_writeable()?;
+        Ok(PyArrayIterMut::new(self))
+    }
+
     pub fn into_py(&self, py: Python) -> PyObject {
         let any: &PyAny = self;
         any.into_py(py)
@@ -495,6 +613,7 @@ where
         Ok(())
     }
 
+    /// Returns a flat view of the array's data, in its native (C or Fortran) element order.
     pub unsafe fn slice(&self) -> PyResult<&[T]> {
         self.is_contiguous()?;
         let obj: &PyArrayObject = self.as_ref();
@@ -504,6 +623,8 @@ where
         Ok(slice)
     }
 
+    /// Returns a mutable flat view of the array's data, in its native (C or Fortran) element
+    /// order.
     pub unsafe fn slice_mut(&self) -> PyResult<&mut [T]> {
         self.is_contiguous()?;
         self.is_writeable()?;
@@ -514,7 +635,113 @@ where
         Ok(slice)
     }
 
+    /// Builds a zero-copy view of a sub-array, following `specs`, one per axis.
+    ///
+    /// An [`SliceSpec::Index`] drops the corresponding axis, while a [`SliceSpec::Range`]
+    /// follows Python's `start:stop:step` slicing semantics. The returned array aliases this
+    /// array's memory, which is kept alive via `set_base_object`.
+    pub fn slice_view<'py>(
+        &self,
+        py: Python<'py>,
+        specs: &[SliceSpec],
+    ) -> PyResult<Bound<'py, Self>> {
+        let api = api(py);
+        let new_from_descriptor = unsafe { *api.new_from_descriptor };
+        let obj: &PyArrayObject = self.as_ref();
+        let shape = self.shape_slice();
+        let strides = self.strides_slice();
+        if specs.len() != shape.len() {
+            return Err(PyValueError::new_err(format!(
+                "bad slice (expected {} specs, found {})",
+                shape.len(),
+                specs.len(),
+            )))
+        }
+
+        let mut new_shape = Vec::<npy_intp>::new();
+        let mut new_strides = Vec::<npy_intp>::new();
+        let mut offset: isize = 0;
+
+        for (k, spec) in specs.iter().enumerate() {
+            let n = shape[k] as isize;
+            let stride = strides[k];
+            match spec {
+                SliceSpec::Index(i) => {
+                    let i = if *i < 0 { i + n } else { *i };
+                    if i < 0 || i >= n {
+                        return Err(PyIndexError::new_err(format!(
+                            "index out of range on axis {} (expected an index in [0, {}), found \
+                                {})",
+                            k,
+                            n,
+                            i,
+                        )))
+                    }
+                    offset += (i as isize) * (stride as isize);
+                },
+                SliceSpec::Range { start, stop, step } => {
+                    if *step == 0 {
+                        return Err(PyValueError::new_err(
+                            "bad slice step (expected a non-zero value, found 0)"
+                        ))
+                    }
+                    let normalize = |v: isize| if v < 0 { v + n } else { v };
+                    let (lo, hi) = if *step > 0 { (0, n) } else { (-1, n - 1) };
+                    let start = start.map(normalize)
+                        .unwrap_or(if *step > 0 { 0 } else { n - 1 })
+                        .clamp(lo, hi);
+                    let stop = stop.map(normalize)
+                        .unwrap_or(if *step > 0 { n } else { -1 })
+                        .clamp(lo, hi);
+                    let len = if *step > 0 {
+                        ((stop - start).max(0) + step - 1) / step
+                    } else {
+                        ((start - stop).max(0) + (-step) - 1) / (-step)
+                    };
+                    offset += start * (stride as isize);
+                    new_shape.push(len as npy_intp);
+                    new_strides.push((stride as isize * step) as npy_intp);
+                },
+            }
+        }
+
+        let dtype = T::dtype(py)?;
+        let flags: c_int = if self.is_writeable().is_ok() { PyArrayFlags::WRITEABLE } else { 0 };
+        let array = new_from_descriptor(
+            api.type_ndarray.as_ptr(),
+            dtype.as_ptr(),
+            new_shape.len() as c_int,
+            new_shape.as_ptr(),
+            new_strides.as_ptr(),
+            unsafe { obj.data.offset(offset) as *mut c_void },
+            flags,
+            std::ptr::null_mut(),
+        );
+        if PyErr::occurred(py) {
+            match PyErr::take(py) {
+                None => unreachable!(),
+                Some(err) => return Err(err),
+            }
+        }
+        unsafe { pyo3::ffi::Py_INCREF(dtype.as_ptr()); }
+        let set_base_object = unsafe { *api.set_base_object };
+        let base = self.as_ptr();
+        set_base_object(array, base);
+        unsafe { pyo3::ffi::Py_INCREF(base); }
+        let array = unsafe { &*(array as *const Self) };
+        let array = unsafe { Py::from_owned_ptr(py, array.0.0.as_ptr()) };
+        Ok(array.into_bound(py))
+    }
+
     pub fn zeros<'py>(py: Python<'py>, shape: &[usize]) -> PyResult<Bound<'py, Self>> {
+        Self::zeros_order(py, shape, Order::C)
+    }
+
+    pub fn zeros_order<'py>(
+        py: Python<'py>,
+        shape: &[usize],
+        order: Order,
+    ) -> PyResult<Bound<'py, Self>> {
         let api = api(py);
         let zeros = unsafe { *api.zeros };
         let dtype = T::dtype(py)?;
@@ -523,7 +750,7 @@ where
             ndim,
             shape.as_ptr() as *const npy_intp,
             dtype.as_ptr(),
-            0,
+            order.into(),
         );
         if PyErr::occurred(py) {
             match PyErr::take(py) {
@@ -542,13 +769,25 @@ where
 impl<T> PyArray<T> {
     fn is_contiguous(&self) -> PyResult<()> {
         let obj: &PyArrayObject = self.as_ref();
-        if obj.flags & PyArrayFlags::C_CONTIGUOUS == 0 {
-            Err(PyValueError::new_err("memory is not C-contiguous"))
+        if obj.flags & (PyArrayFlags::C_CONTIGUOUS | PyArrayFlags::F_CONTIGUOUS) == 0 {
+            Err(PyValueError::new_err("memory is not contiguous"))
         } else {
             Ok(())
         }
     }
 
+    // Column-major (Fortran) strides for a C-shape, in bytes.
+    fn fortran_strides(shape: &[npy_intp]) -> Vec<npy_intp> {
+        let itemsize = std::mem::size_of::<T>() as npy_intp;
+        let mut strides = vec![0; shape.len()];
+        let mut stride = itemsize;
+        for (k, &extent) in shape.iter().enumerate() {
+            strides[k] = stride;
+            stride *= extent.max(1);
+        }
+        strides
+    }
+
     fn is_writeable(&self) -> PyResult<()> {
         let obj: &PyArrayObject = self.as_ref();
         if obj.flags & PyArrayFlags::WRITEABLE == 0 {
@@ -652,6 +891,413 @@ unsafe impl<T> PyNativeType for PyArray<T> {
 }
 
 
+// ===============================================================================================
+//
+// Slicing.
+//
+// ===============================================================================================
+
+/// A single-axis slice specification, for [`PyArray::slice_view`].
+pub enum SliceSpec {
+    /// A single index. The corresponding axis is dropped from the resulting view.
+    Index(isize),
+    /// A `start:stop:step` range, following Python's slicing semantics.
+    Range {
+        start: Option<isize>,
+        stop: Option<isize>,
+        step: isize,
+    },
+}
+
+
+// ===============================================================================================
+//
+// Strided iterators.
+//
+// ===============================================================================================
+
+// Shared multi-index walk, carrying from the last axis into the previous ones, Python (row-major)
+// order.
+fn advance(index: &mut [usize], shape: &[usize]) -> bool {
+    for k in (0..shape.len()).rev() {
+        index[k] += 1;
+        if index[k] < shape[k] {
+            return false;
+        }
+        index[k] = 0;
+    }
+    true
+}
+
+pub struct PyArrayIter<'a, T> {
+    array: &'a PyArray<T>,
+    index: Vec<usize>,
+    shape: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> PyArrayIter<'a, T>
+where
+    T: Copy + Dtype,
+{
+    fn new(array: &'a PyArray<T>) -> Self {
+        let shape = array.shape();
+        let done = shape.iter().any(|&n| n == 0);
+        let index = vec![0; shape.len()];
+        Self { array, index, shape, done }
+    }
+}
+
+impl<'a, T> Iterator for PyArrayIter<'a, T>
+where
+    T: Copy + Dtype,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let value = self.array.get_nd(&self.index)
+            .expect("index is in bounds by construction");
+        self.done = advance(&mut self.index, &self.shape);
+        Some(value)
+    }
+}
+
+pub struct PyArrayIterMut<'a, T> {
+    array: &'a PyArray<T>,
+    index: Vec<usize>,
+    shape: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> PyArrayIterMut<'a, T>
+where
+    T: Copy + Dtype,
+{
+    fn new(array: &'a PyArray<T>) -> Self {
+        let shape = array.shape();
+        let done = shape.iter().any(|&n| n == 0);
+        let index = vec![0; shape.len()];
+        Self { array, index, shape, done }
+    }
+}
+
+impl<'a, T> Iterator for PyArrayIterMut<'a, T>
+where
+    T: Copy + Dtype,
+{
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.done {
+            return None;
+        }
+        let data = self.array.data_nd(&self.index)
+            .expect("index is in bounds by construction");
+        let value = unsafe { &mut *(data as *mut T) };
+        self.done = advance(&mut self.index, &self.shape);
+        Some(value)
+    }
+}
+
+
+// ===============================================================================================
+//
+// Broadcasting.
+//
+// ===============================================================================================
+
+/// Computes the broadcast shape of two array shapes, following NumPy's rules.
+///
+/// The shorter shape is right-aligned and padded with leading 1s; for each axis the output
+/// extent is the non-1 value when the two differ, the common value when they agree, and a
+/// mismatch where neither is 1 is a `PyValueError`.
+pub fn broadcast_shapes(a: &[usize], b: &[usize]) -> PyResult<Vec<usize>> {
+    let n = a.len().max(b.len());
+    let mut shape = Vec::with_capacity(n);
+    for k in 0..n {
+        let da = if k < n - a.len() { 1 } else { a[k - (n - a.len())] };
+        let db = if k < n - b.len() { 1 } else { b[k - (n - b.len())] };
+        let d = if da == db {
+            da
+        } else if da == 1 {
+            db
+        } else if db == 1 {
+            da
+        } else {
+            return Err(PyValueError::new_err(format!(
+                "incompatible shapes for broadcasting ({:?} and {:?})",
+                a,
+                b,
+            )))
+        };
+        shape.push(d);
+    }
+    Ok(shape)
+}
+
+fn broadcast_strides(shape: &[usize], in_shape: &[usize], in_strides: &[npy_intp]) -> Vec<isize> {
+    let offset = shape.len() - in_shape.len();
+    (0..shape.len())
+        .map(|k| {
+            if k < offset {
+                0
+            } else {
+                let i = k - offset;
+                if in_shape[i] == 1 && shape[k] != 1 {
+                    0
+                } else {
+                    in_strides[i] as isize
+                }
+            }
+        })
+        .collect()
+}
+
+/// Iterates element-wise over two, possibly differently-shaped, arrays, broadcasting them
+/// together following NumPy's rules.
+///
+/// A broadcast axis (size 1 in one input but not in the output) is walked with a stride of 0,
+/// re-reading the same element as the shared index advances, so that neither input is
+/// materialized to the broadcast shape.
+pub struct BroadcastIter<'a, T> {
+    a: &'a PyArray<T>,
+    b: &'a PyArray<T>,
+    shape: Vec<usize>,
+    strides_a: Vec<isize>,
+    strides_b: Vec<isize>,
+    index: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> BroadcastIter<'a, T>
+where
+    T: Copy + Dtype,
+{
+    pub fn new(a: &'a PyArray<T>, b: &'a PyArray<T>) -> PyResult<Self> {
+        let shape_a = a.shape();
+        let shape_b = b.shape();
+        let shape = broadcast_shapes(&shape_a, &shape_b)?;
+        let strides_a = broadcast_strides(&shape, &shape_a, a.strides_slice());
+        let strides_b = broadcast_strides(&shape, &shape_b, b.strides_slice());
+        let done = shape.iter().any(|&n| n == 0);
+        let index = vec![0; shape.len()];
+        Ok(Self { a, b, shape, strides_a, strides_b, index, done })
+    }
+
+    /// The broadcast output shape.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+}
+
+impl<'a, T> Iterator for BroadcastIter<'a, T>
+where
+    T: Copy + Dtype,
+{
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<(T, T)> {
+        if self.done {
+            return None;
+        }
+        let offset_a: isize = std::iter::zip(&self.index, &self.strides_a)
+            .map(|(i, s)| (*i as isize) * s)
+            .sum();
+        let offset_b: isize = std::iter::zip(&self.index, &self.strides_b)
+            .map(|(i, s)| (*i as isize) * s)
+            .sum();
+        let obj_a: &PyArrayObject = self.a.as_ref();
+        let obj_b: &PyArrayObject = self.b.as_ref();
+        let va = unsafe { *(obj_a.data.offset(offset_a) as *const T) };
+        let vb = unsafe { *(obj_b.data.offset(offset_b) as *const T) };
+        self.done = advance(&mut self.index, &self.shape);
+        Some((va, vb))
+    }
+}
+
+
+// ===============================================================================================
+//
+// Borrow-tracked views.
+//
+// ===============================================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum BorrowState {
+    Shared,
+    Exclusive,
+}
+
+// A single live borrow, tracked as the byte range it covers on whatever data buffer it aliases
+// (see `buffer_range`), rather than as a wrapper-object or bare-pointer identity.
+struct Borrow {
+    range: (usize, usize),
+    state: BorrowState,
+}
+
+static BORROWS: GILOnceCell<Mutex<Vec<Borrow>>> = GILOnceCell::new();
+
+fn borrows(py: Python) -> &Mutex<Vec<Borrow>> {
+    BORROWS.get_or_init(py, || Mutex::new(Vec::new()))
+}
+
+fn overlaps(a: (usize, usize), b: (usize, usize)) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+// The byte range, on the underlying data buffer, that `array` aliases -- not the ndarray wrapper
+// object's identity, nor its own (possibly offset) data pointer alone. Two distinct ndarray
+// objects overlapping on the same buffer (e.g. a view and its parent, or two overlapping slices
+// of the same parent) must be seen as conflicting, or aliasing between them would go undetected;
+// a bare pointer equality check misses exactly that case for `slice_view`'s offset views.
+fn buffer_range<T>(array: &PyArray<T>) -> (usize, usize) {
+    let obj: &PyArrayObject = array.as_ref();
+    let itemsize = std::mem::size_of::<T>() as isize;
+    let mut lo: isize = 0;
+    let mut hi: isize = 0;
+    for (&n, &stride) in array.shape_slice().iter().zip(array.strides_slice()) {
+        if n <= 1 {
+            continue
+        }
+        let span = (n as isize - 1) * (stride as isize);
+        if span > 0 {
+            hi += span;
+        } else {
+            lo += span;
+        }
+    }
+    let data = obj.data as isize;
+    ((data + lo) as usize, (data + hi + itemsize) as usize)
+}
+
+fn acquire_shared<T>(array: &PyArray<T>) -> PyResult<()> {
+    let range = buffer_range(array);
+    let mut borrows = borrows(array.py()).lock().unwrap();
+    let conflict = borrows.iter()
+        .any(|b| b.state == BorrowState::Exclusive && overlaps(range, b.range));
+    if conflict {
+        return Err(PyValueError::new_err("array is already mutably borrowed"))
+    }
+    borrows.push(Borrow { range, state: BorrowState::Shared });
+    Ok(())
+}
+
+fn release_shared<T>(array: &PyArray<T>) {
+    let range = buffer_range(array);
+    let mut borrows = borrows(array.py()).lock().unwrap();
+    if let Some(i) = borrows.iter()
+        .position(|b| b.range == range && b.state == BorrowState::Shared) {
+        borrows.remove(i);
+    }
+}
+
+fn acquire_exclusive<T>(array: &PyArray<T>) -> PyResult<()> {
+    let range = buffer_range(array);
+    let mut borrows = borrows(array.py()).lock().unwrap();
+    let conflict = borrows.iter().any(|b| overlaps(range, b.range));
+    if conflict {
+        return Err(PyValueError::new_err("array is already borrowed"))
+    }
+    borrows.push(Borrow { range, state: BorrowState::Exclusive });
+    Ok(())
+}
+
+fn release_exclusive<T>(array: &PyArray<T>) {
+    let range = buffer_range(array);
+    let mut borrows = borrows(array.py()).lock().unwrap();
+    if let Some(i) = borrows.iter()
+        .position(|b| b.range == range && b.state == BorrowState::Exclusive) {
+        borrows.remove(i);
+    }
+}
+
+/// A checked, read-only view of a contiguous [`PyArray<T>`].
+///
+/// Construction (via `extract`) registers a shared borrow of the underlying buffer, refused if
+/// an exclusive ([`PyReadWriteArray`]) view of the same buffer is already live; the borrow is
+/// released on `Drop`. This rules out, at runtime, the aliasing that `get`/`set` on a raw
+/// `PyArray<T>` cannot detect.
+pub struct PyReadonlyArray<'py, T> {
+    array: &'py PyArray<T>,
+}
+
+impl<'py, T> PyReadonlyArray<'py, T>
+where
+    T: Copy + Dtype,
+{
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { self.array.slice() }.expect("checked at construction")
+    }
+}
+
+impl<'py, T> Drop for PyReadonlyArray<'py, T> {
+    fn drop(&mut self) {
+        release_shared(self.array);
+    }
+}
+
+impl<'py, T> FromPyObject<'py> for PyReadonlyArray<'py, T>
+where
+    T: Copy + Dtype,
+{
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let array: &'py PyArray<T> = obj.extract()?;
+        acquire_shared(array)?;
+        if let Err(err) = unsafe { array.slice() } {
+            release_shared(array);
+            return Err(err);
+        }
+        Ok(Self { array })
+    }
+}
+
+/// A checked, read-write view of a contiguous [`PyArray<T>`].
+///
+/// Construction (via `extract`) registers an exclusive borrow of the underlying buffer, refused
+/// if any other [`PyReadonlyArray`] or [`PyReadWriteArray`] view of the same buffer is already
+/// live; the borrow is released on `Drop`.
+pub struct PyReadWriteArray<'py, T> {
+    array: &'py PyArray<T>,
+}
+
+impl<'py, T> PyReadWriteArray<'py, T>
+where
+    T: Copy + Dtype,
+{
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { self.array.slice() }.expect("checked at construction")
+    }
+
+    pub fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe { self.array.slice_mut() }.expect("checked at construction")
+    }
+}
+
+impl<'py, T> Drop for PyReadWriteArray<'py, T> {
+    fn drop(&mut self) {
+        release_exclusive(self.array);
+    }
+}
+
+impl<'py, T> FromPyObject<'py> for PyReadWriteArray<'py, T>
+where
+    T: Copy + Dtype,
+{
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let array: &'py PyArray<T> = obj.extract()?;
+        acquire_exclusive(array)?;
+        if let Err(err) = unsafe { array.slice_mut() } {
+            release_exclusive(array);
+            return Err(err);
+        }
+        Ok(Self { array })
+    }
+}
+
+
 // ===============================================================================================
 //
 // Bound interface.
@@ -670,57 +1316,102 @@ pub trait PyArrayMethods<T> {
     // Typed methods.
     fn get(&self, index: usize) -> PyResult<T>;
     fn set(&self, index: usize, value: T) -> PyResult<()>;
+    fn get_nd(&self, index: &[usize]) -> PyResult<T>;
+    fn set_nd(&self, index: &[usize], value: T) -> PyResult<()>;
     unsafe fn slice(&self) -> PyResult<&[T]>;
     unsafe fn slice_mut(&self) -> PyResult<&mut [T]>;
 }
 
+// `Bound`-native: operates directly on the raw object behind `self.as_ptr()`, never
+// materializing a `&PyArray<T>`/`&PyUntypedArray` gil-ref.
 impl<'py, T> PyArrayMethods<T> for Bound<'py, PyArray<T>>
 where
     T: Copy + Dtype,
 {
     #[inline]
     fn dtype(&self) -> PyObject {
-        self.as_gil_ref().0.dtype()
+        unsafe { Py::<PyAny>::from_borrowed_ptr(self.py(), self.as_ptr()) }
     }
 
     #[inline]
     fn ndim(&self) -> usize {
-        self.as_gil_ref().0.ndim()
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        obj.nd as usize
     }
 
     #[inline]
     fn readonly(&self) {
-        self.as_gil_ref().0.readonly()
+        let obj: &mut PyArrayObject = unsafe { &mut *(self.as_ptr() as *mut PyArrayObject) };
+        obj.flags &= !PyArrayFlags::WRITEABLE;
     }
 
     #[inline]
     fn shape(&self) -> Vec<usize> {
-        self.as_gil_ref().0.shape()
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        raw_shape(obj).iter().map(|v| *v as usize).collect()
     }
 
     #[inline]
     fn size(&self) -> usize {
-        self.as_gil_ref().0.size()
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        raw_size(obj)
     }
 
     #[inline]
     fn get(&self, index: usize) -> PyResult<T> {
-        self.as_gil_ref().get(index)
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        let data = raw_data(obj, index)?;
+        Ok(unsafe { *(data as *const T) })
     }
 
     #[inline]
     fn set(&self, index: usize, value: T) -> PyResult<()> {
-        self.as_gil_ref().set(index, value)
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        if obj.flags & PyArrayFlags::WRITEABLE == 0 {
+            return Err(PyValueError::new_err("assignment destination is read-only"))
+        }
+        let data = raw_data(obj, index)?;
+        unsafe { *(data as *mut T) = value; }
+        Ok(())
+    }
+
+    #[inline]
+    fn get_nd(&self, index: &[usize]) -> PyResult<T> {
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        let data = raw_data_nd(obj, index)?;
+        Ok(unsafe { *(data as *const T) })
+    }
+
+    #[inline]
+    fn set_nd(&self, index: &[usize], value: T) -> PyResult<()> {
+        let obj: &PyArrayObject = unsafe { &*(self.as_ptr() as *mut PyArrayObject) };
+        if obj.flags & PyArrayFlags::WRITEABLE == 0 {
+            return Err(PyValueError::new_err("assignment destination is read-only"))
+        }
+        let data = raw_data_nd(obj, index)?;
+        unsafe { *(data as *mut T) = value; }
+        Ok(())
     }
 
     #[inline]
     unsafe fn slice(&self) -> PyResult<&[T]> {
-        self.as_gil_ref().slice()
+        let obj: &PyArrayObject = &*(self.as_ptr() as *mut PyArrayObject);
+        if obj.flags & (PyArrayFlags::C_CONTIGUOUS | PyArrayFlags::F_CONTIGUOUS) == 0 {
+            return Err(PyValueError::new_err("memory is not contiguous"))
+        }
+        Ok(std::slice::from_raw_parts(obj.data as *const T, raw_size(obj)))
     }
 
     #[inline]
     unsafe fn slice_mut(&self) -> PyResult<&mut [T]> {
-        self.as_gil_ref().slice_mut()
+        let obj: &PyArrayObject = &*(self.as_ptr() as *mut PyArrayObject);
+        if obj.flags & (PyArrayFlags::C_CONTIGUOUS | PyArrayFlags::F_CONTIGUOUS) == 0 {
+            return Err(PyValueError::new_err("memory is not contiguous"))
+        }
+        if obj.flags & PyArrayFlags::WRITEABLE == 0 {
+            return Err(PyValueError::new_err("assignment destination is read-only"))
+        }
+        Ok(std::slice::from_raw_parts_mut(obj.data as *mut T, raw_size(obj)))
     }
 }
 
@@ -792,6 +1483,114 @@ impl Dtype for CVertex {
 }
 
 
+// ===============================================================================================
+//
+// Runtime registration of structured dtypes.
+//
+// ===============================================================================================
+
+/// Marker for a `#[repr(C)]` record type whose NumPy dtype is registered at runtime via
+/// [`register_dtype`], rather than hardcoded in [`initialise`].
+///
+/// This turns the closed set of built-in record dtypes (`dtype_shell`, `dtype_state`,
+/// `dtype_vertex`) into an extensible mechanism: downstream code describes a new record layout
+/// once, and then uses it like any other [`Dtype`] (e.g. as `PyArray<T>`'s element type).
+pub trait Registered: 'static {}
+
+impl<T> Dtype for T
+where
+    T: Registered,
+{
+    #[inline]
+    fn dtype(py: Python) -> PyResult<PyObject> {
+        registered_dtype::<T>(py)
+    }
+}
+
+/// A structured dtype field, following the `(name, format[, shape])` convention used when
+/// constructing the built-in record dtypes.
+pub struct DtypeField {
+    pub name: &'static str,
+    pub format: &'static str,
+    pub shape: Option<usize>,
+    /// This field's expected byte offset within `T` (e.g. from `memoffset::offset_of!`), checked
+    /// against the dtype NumPy actually builds.
+    pub offset: usize,
+}
+
+static DTYPE_REGISTRY: GILOnceCell<Mutex<HashMap<TypeId, PyObject>>> = GILOnceCell::new();
+
+fn dtype_registry(py: Python) -> &Mutex<HashMap<TypeId, PyObject>> {
+    DTYPE_REGISTRY.get_or_init(py, || Mutex::new(HashMap::new()))
+}
+
+/// Registers a NumPy structured dtype for a [`Registered`] record type `T`, validating it
+/// against `std::mem::size_of::<T>()` and each field's expected offset.
+pub fn register_dtype<T>(py: Python, fields: &[DtypeField]) -> PyResult<PyObject>
+where
+    T: Registered,
+{
+    let numpy = PyModule::import_bound(py, "numpy")?;
+    let dtype_ctor = numpy.getattr("dtype")?;
+
+    let args: Vec<PyObject> = fields.iter()
+        .map(|field| -> PyObject {
+            match field.shape {
+                None => (field.name, field.format).into_py(py),
+                Some(shape) => (field.name, field.format, shape).into_py(py),
+            }
+        })
+        .collect();
+    let dtype = dtype_ctor.call1((args,))?;
+
+    let itemsize: usize = dtype.getattr("itemsize")?.extract()?;
+    let expected = std::mem::size_of::<T>();
+    if itemsize != expected {
+        return Err(PyValueError::new_err(format!(
+            "bad dtype for '{}' (expected a size of {} bytes, found {})",
+            std::any::type_name::<T>(),
+            expected,
+            itemsize,
+        )))
+    }
+
+    let np_fields = dtype.getattr("fields")?;
+    for field in fields {
+        let entry = np_fields.get_item(field.name)?;
+        let offset: usize = entry.get_item(1)?.extract()?;
+        if offset != field.offset {
+            return Err(PyValueError::new_err(format!(
+                "bad offset for field '{}' of '{}' (expected {}, found {})",
+                field.name,
+                std::any::type_name::<T>(),
+                field.offset,
+                offset,
+            )))
+        }
+    }
+
+    let dtype: PyObject = dtype.into_py(py);
+    dtype_registry(py).lock().unwrap().insert(TypeId::of::<T>(), dtype.clone_ref(py));
+    Ok(dtype)
+}
+
+/// Returns the NumPy dtype previously registered for `T` via [`register_dtype`].
+pub fn registered_dtype<T>(py: Python) -> PyResult<PyObject>
+where
+    T: Registered,
+{
+    dtype_registry(py)
+        .lock()
+        .unwrap()
+        .get(&TypeId::of::<T>())
+        .map(|dtype| dtype.clone_ref(py))
+        .ok_or_else(|| PyValueError::new_err(format!(
+            "dtype not registered for '{}' (expected a prior call to register_dtype)",
+            std::any::type_name::<T>(),
+        )))
+}
+
+
 //================================================================================================
 // Control flags for Numpy arrays.
 //================================================================================================
@@ -803,9 +1602,29 @@ pub enum PyArrayFlags {
 
 impl PyArrayFlags {
     pub const C_CONTIGUOUS: c_int = 0x0001;
+    pub const F_CONTIGUOUS: c_int = 0x0002;
     pub const WRITEABLE:    c_int = 0x0400;
 }
 
+/// Memory layout of an array's elements.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Order {
+    /// Row-major (C) order: the last axis varies fastest.
+    #[default]
+    C,
+    /// Column-major (Fortran) order: the first axis varies fastest.
+    Fortran,
+}
+
+impl From<Order> for c_int {
+    fn from(value: Order) -> Self {
+        match value {
+            Order::C => 0,
+            Order::Fortran => 1,
+        }
+    }
+}
+
 impl From<PyArrayFlags> for c_int {
     fn from(value: PyArrayFlags) -> Self {
         match value {
@@ -886,13 +1705,29 @@ pub trait PyScalarMethods<T> {
     fn get(&self) -> PyResult<T>;
 }
 
+// `Bound`-native: calls the C API directly on `self.as_ptr()`, never materializing a
+// `&PyScalar<T>` gil-ref.
 impl<'py, T> PyScalarMethods<T> for Bound<'py, PyScalar<T>>
 where
     T: Copy + Default + Dtype,
 {
     #[inline]
     fn get(&self) -> PyResult<T> {
-        self.as_gil_ref().get()
+        let py = self.py();
+        let api = api(py);
+        let scalar_as_ctype = unsafe { *api.scalar_as_ctype };
+        let mut data = T::default();
+        scalar_as_ctype(
+            self.as_ptr(),
+            &mut data as *mut T as *mut c_void,
+        );
+        if PyErr::occurred(py) {
+            match PyErr::take(py) {
+                None => unreachable!(),
+                Some(err) => return Err(err),
+            }
+        }
+        Ok(data)
     }
 }
 
@@ -903,6 +1738,84 @@ where
 //
 // ===============================================================================================
 
+/// A scalar-or-array argument, resolved against a shared iteration count.
+///
+/// Unlike [`ArrayOrFloat`], which indexes its backing array unconditionally, a [`Vectorized`] is
+/// only ever built by [`Vectorized::from_python`], which checks upfront that every vectorized
+/// argument to a call shares a common length. Per-element access then goes through
+/// [`Vectorized::get_unchecked`], with no further bounds checking.
+pub enum Vectorized<'a, T> {
+    Scalar(T),
+    Slice(&'a [T]),
+}
+
+impl<'a, T> Vectorized<'a, T>
+where
+    T: Copy + Dtype,
+{
+    /// Returns the `i`-th element, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `i` must be less than `self.len()`.
+    pub unsafe fn get_unchecked(&self, i: usize) -> T {
+        match self {
+            Self::Scalar(value) => *value,
+            Self::Slice(slice) => *slice.get_unchecked(i),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Scalar(_) => 1,
+            Self::Slice(slice) => slice.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, T> Vectorized<'a, T>
+where
+    T: Copy + Dtype + FromPyObject<'a>,
+{
+    /// Resolves a scalar-or-array Python argument against a shared `iterations` count.
+    ///
+    /// A Python scalar, or a length-1 array, is always a [`Vectorized::Scalar`]. A longer, or
+    /// empty, array is a [`Vectorized::Slice`]: the first one encountered sets `*iterations` to
+    /// `Some(length)` (including `Some(0)`, for an empty array), and any later argument whose
+    /// length disagrees is reported as a `ValueError` naming `name`. `*iterations` stays `None`
+    /// for as long as every argument seen so far is a scalar, so it is never conflated with an
+    /// empty array's length of `0`.
+    pub fn from_python(
+        obj: &'a Bound<PyAny>,
+        name: &str,
+        iterations: &mut Option<usize>,
+    ) -> PyResult<Self> {
+        if let Ok(value) = obj.extract::<T>() {
+            return Ok(Self::Scalar(value));
+        }
+        let array: &'a PyArray<T> = obj.extract()?;
+        if array.size() == 1 {
+            return Ok(Self::Scalar(array.get(0)?));
+        }
+        let slice = unsafe { array.slice()? };
+        match *iterations {
+            None => *iterations = Some(slice.len()),
+            Some(expected) if expected != slice.len() => value_error!(
+                "bad size for '{}' (expected a length-{} array, found a length-{} array)",
+                name,
+                expected,
+                slice.len(),
+            ),
+            Some(_) => {},
+        }
+        Ok(Self::Slice(slice))
+    }
+}
+
 #[derive(pyo3::FromPyObject)]
 pub enum ArrayOrFloat<'a> {
     Array(&'a PyArray<Float>),
@@ -938,6 +1851,20 @@ pub enum ArrayOrFloat3<'a> {
     Float3(Float3),
 }
 
+impl<'py> FromPyObject<'py> for Float3 {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let array: &PyArray<Float> = obj.extract()?;
+        let shape = array.shape();
+        if shape != [3] {
+            return Err(PyValueError::new_err(format!(
+                "bad shape for Float3 (expected [3], found {:?})",
+                shape,
+            )))
+        }
+        Ok(Float3::new(array.get(0)?, array.get(1)?, array.get(2)?))
+    }
+}
+
 impl IntoPy<PyObject> for Float3 {
     fn into_py(self, py: Python) -> PyObject {
         let result = PyArray::<Float>::empty(py, &[3]).unwrap();
@@ -949,6 +1876,35 @@ impl IntoPy<PyObject> for Float3 {
     }
 }
 
+impl<'py> FromPyObject<'py> for Float3x3 {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let array: &PyArray<Float> = obj.extract()?;
+        let shape = array.shape();
+        let mut data = [0.0 as Float; 9];
+        if shape == [3, 3] {
+            for i in 0..3 {
+                for j in 0..3 {
+                    data[i * 3 + j] = array.get_nd(&[i, j])?;
+                }
+            }
+        } else if shape == [9] {
+            for (k, value) in data.iter_mut().enumerate() {
+                *value = array.get(k)?;
+            }
+        } else {
+            return Err(PyValueError::new_err(format!(
+                "bad shape for Float3x3 (expected [3, 3] or [9], found {:?})",
+                shape,
+            )))
+        }
+        Ok(Float3x3::new(
+            data[0], data[1], data[2],
+            data[3], data[4], data[5],
+            data[6], data[7], data[8],
+        ))
+    }
+}
+
 impl IntoPy<PyObject> for Float3x3 {
     fn into_py(self, py: Python) -> PyObject {
         let result = PyArray::<Float>::empty(py, &[3, 3]).unwrap();
@@ -12,8 +12,30 @@ use crate::physics::process::rayleigh::{RayleighMode, sample::RayleighSampler};
 use pyo3::prelude::*;
 use pyo3::exceptions::PyTypeError;
 use pyo3::types::PyDict;
+use super::ctrlc_catched;
 use super::macros::{key_error, value_error};
 use super::materials::PyMaterialRecord;
+use super::numpy::{PyArray, Vectorized};
+use super::rand::PyRandomStream;
+
+
+// ===============================================================================================
+// Shared helper for scalar-or-array results.
+// ===============================================================================================
+
+/// Packs `values` back into a Python object: a plain scalar if `iterations` is `None` (i.e.
+/// every input was a scalar), or a length-`iterations` array otherwise (possibly empty, if some
+/// vectorized input was an empty array).
+fn vectorized_result(py: Python, iterations: Option<usize>, values: Vec<Float>) -> Result<PyObject> {
+    match iterations {
+        None => Ok(values[0].into_py(py)),
+        Some(n) => {
+            let array = PyArray::<Float>::from_iter(py, &[n], values.into_iter())?;
+            let array: &PyAny = &array;
+            Ok(array.into_py(py))
+        },
+    }
+}
 
 
 // ===============================================================================================
@@ -184,64 +206,121 @@ impl PyComptonProcess {
 
     fn cross_section(
         &self,
-        energy: Float,
+        py: Python,
+        energy: &Bound<PyAny>,
         material: PyRef<PyMaterialRecord>,
         energy_min: Option<Float>,
         energy_max: Option<Float>
-    ) -> Result<Float> {
+    ) -> Result<PyObject> {
         let electrons = Self::get_electrons(material.py(), &material)?;
-        self.computer.cross_section(
-            energy,
-            energy_min,
-            energy_max,
-            electrons,
-        )
+        let mut iterations: Option<usize> = None;
+        let energy = Vectorized::<Float>::from_python(energy, "energy", &mut iterations)?;
+        let mut values = Vec::with_capacity(energy.len());
+        for i in 0..energy.len() {
+            let energy_i = unsafe { energy.get_unchecked(i) };
+            values.push(self.computer.cross_section(
+                energy_i,
+                energy_min,
+                energy_max,
+                electrons,
+            )?);
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        vectorized_result(py, iterations, values)
     }
 
     fn dcs(
         &self,
-        energy_in: Float,
-        energy_out: Float,
+        py: Python,
+        energy_in: &Bound<PyAny>,
+        energy_out: &Bound<PyAny>,
         material: PyRef<PyMaterialRecord>
-    ) -> Result<Float> {
+    ) -> Result<PyObject> {
         let electrons = Self::get_electrons(material.py(), &material)?;
-        Ok(self.computer.dcs(
-            energy_in,
-            energy_out,
-            electrons,
-        ))
+        let mut iterations: Option<usize> = None;
+        let energy_in = Vectorized::<Float>::from_python(energy_in, "energy_in", &mut iterations)?;
+        let energy_out = Vectorized::<Float>::from_python(
+            energy_out, "energy_out", &mut iterations
+        )?;
+        let n = iterations.unwrap_or(1);
+        let mut values = Vec::with_capacity(n);
+        for i in 0..n {
+            let energy_in_i = unsafe { energy_in.get_unchecked(i % energy_in.len()) };
+            let energy_out_i = unsafe { energy_out.get_unchecked(i % energy_out.len()) };
+            values.push(self.computer.dcs(energy_in_i, energy_out_i, electrons));
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        vectorized_result(py, iterations, values)
     }
 
-    fn dcs_support(&self, energy_in: Float) -> (Float, Float) {
-        self.computer.dcs_support(energy_in)
+    fn dcs_support(&self, py: Python, energy_in: &Bound<PyAny>) -> Result<(PyObject, PyObject)> {
+        let mut iterations: Option<usize> = None;
+        let energy_in = Vectorized::<Float>::from_python(energy_in, "energy_in", &mut iterations)?;
+        let mut los = Vec::with_capacity(energy_in.len());
+        let mut his = Vec::with_capacity(energy_in.len());
+        for i in 0..energy_in.len() {
+            let energy_in_i = unsafe { energy_in.get_unchecked(i) };
+            let (lo, hi) = self.computer.dcs_support(energy_in_i);
+            los.push(lo);
+            his.push(hi);
+
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        Ok((
+            vectorized_result(py, iterations, los)?,
+            vectorized_result(py, iterations, his)?,
+        ))
     }
 
     fn sample(
         &self,
-        energy_in: Float,
-        material: PyRef<PyMaterialRecord>
+        py: Python,
+        energy_in: &Bound<PyAny>,
+        material: PyRef<PyMaterialRecord>,
+        stream: Option<Py<PyRandomStream>>,
     )
-    -> Result<(Float, Float, Float)> {
-        // XXX Use PyRandomStream?
-        // XXX Vectorize this method?
-
-        // Get / format inputs.
-        let mut rng = rand::thread_rng();
-        let momentum_in = Float3::new(0.0, 0.0, energy_in);
-
-        // Generate a sample.
-        let py = material.py();
-        let sample = self.sampler.sample(
-            &mut rng,
-            momentum_in,
-            material.get(py)?,
-            None,
-        )?;
+    -> Result<(PyObject, PyObject, PyObject)> {
+        let material = material.get(material.py())?;
+
+        let default_stream: Py<PyRandomStream>;
+        let stream = match stream.as_ref() {
+            None => {
+                default_stream = Py::new(py, PyRandomStream::new(None)?)?;
+                &default_stream
+            },
+            Some(stream) => stream,
+        };
+        let rng: &mut PyRandomStream = &mut stream.borrow_mut(py);
+
+        let mut iterations: Option<usize> = None;
+        let energy_in = Vectorized::<Float>::from_python(energy_in, "energy_in", &mut iterations)?;
+        let mut energies_out = Vec::with_capacity(energy_in.len());
+        let mut cos_thetas = Vec::with_capacity(energy_in.len());
+        let mut weights = Vec::with_capacity(energy_in.len());
+        for i in 0..energy_in.len() {
+            let energy_in_i = unsafe { energy_in.get_unchecked(i) };
+            let momentum_in = Float3::new(0.0, 0.0, energy_in_i);
+            let sample = self.sampler.sample(rng, momentum_in, material, None)?;
+            let energy_out = sample.momentum_out.norm();
+            energies_out.push(energy_out);
+            cos_thetas.push(sample.momentum_out.2 / energy_out);
+            weights.push(sample.weight);
 
-        // Format outputs.
-        let energy_out = sample.momentum_out.norm();
-        let cos_theta = sample.momentum_out.2 / energy_out;
-        Ok((energy_out, cos_theta, sample.weight))
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        Ok((
+            vectorized_result(py, iterations, energies_out)?,
+            vectorized_result(py, iterations, cos_thetas)?,
+            vectorized_result(py, iterations, weights)?,
+        ))
     }
 }
 
@@ -277,41 +356,87 @@ impl PyRayleighProcess {
 
     fn cross_section(
         &self,
-        energy: Float, // XXX Vectorize these functions.
+        py: Python,
+        energy: &Bound<PyAny>,
         material: PyRef<PyMaterialRecord>,
-    ) -> Result<Float> {
-        let py = material.py();
-        let cs = match material.get(py)?.rayleigh_cross_section() {
-            None => 0.0,
-            Some(table) => table.interpolate(energy),
-        };
-        Ok(cs)
+    ) -> Result<PyObject> {
+        let material = material.get(material.py())?;
+        let mut iterations: Option<usize> = None;
+        let energy = Vectorized::<Float>::from_python(energy, "energy", &mut iterations)?;
+        let mut values = Vec::with_capacity(energy.len());
+        for i in 0..energy.len() {
+            let energy_i = unsafe { energy.get_unchecked(i) };
+            let cs = match material.rayleigh_cross_section() {
+                None => 0.0,
+                Some(table) => table.interpolate(energy_i),
+            };
+            values.push(cs);
+
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        vectorized_result(py, iterations, values)
     }
 
     fn dcs(
         &self,
-        energy: Float,
-        cos_theta: Float,
+        py: Python,
+        energy: &Bound<PyAny>,
+        cos_theta: &Bound<PyAny>,
         material: PyRef<PyMaterialRecord>
-    ) -> Result<Float> {
-        let py = material.py();
-        let material = material.get(py)?;
-        self.0.dcs(energy, cos_theta, material)
+    ) -> Result<PyObject> {
+        let material = material.get(material.py())?;
+        let mut iterations: Option<usize> = None;
+        let energy = Vectorized::<Float>::from_python(energy, "energy", &mut iterations)?;
+        let cos_theta = Vectorized::<Float>::from_python(
+            cos_theta, "cos_theta", &mut iterations
+        )?;
+        let n = iterations.unwrap_or(1);
+        let mut values = Vec::with_capacity(n);
+        for i in 0..n {
+            let energy_i = unsafe { energy.get_unchecked(i % energy.len()) };
+            let cos_theta_i = unsafe { cos_theta.get_unchecked(i % cos_theta.len()) };
+            values.push(self.0.dcs(energy_i, cos_theta_i, material)?);
+
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        vectorized_result(py, iterations, values)
     }
 
     fn sample(
         &self,
-        energy: Float,
-        material: PyRef<PyMaterialRecord>
+        py: Python,
+        energy: &Bound<PyAny>,
+        material: PyRef<PyMaterialRecord>,
+        stream: Option<Py<PyRandomStream>>,
     )
-    -> Result<Float> {
-        let py = material.py();
-        let mut rng = rand::thread_rng();
-        let cos_theta = self.0.sample_angle(
-            &mut rng,
-            energy,
-            material.get(py)?
-        )?;
-        Ok(cos_theta)
+    -> Result<PyObject> {
+        let material = material.get(material.py())?;
+
+        let default_stream: Py<PyRandomStream>;
+        let stream = match stream.as_ref() {
+            None => {
+                default_stream = Py::new(py, PyRandomStream::new(None)?)?;
+                &default_stream
+            },
+            Some(stream) => stream,
+        };
+        let rng: &mut PyRandomStream = &mut stream.borrow_mut(py);
+
+        let mut iterations: Option<usize> = None;
+        let energy = Vectorized::<Float>::from_python(energy, "energy", &mut iterations)?;
+        let mut values = Vec::with_capacity(energy.len());
+        for i in 0..energy.len() {
+            let energy_i = unsafe { energy.get_unchecked(i) };
+            values.push(self.0.sample_angle(rng, energy_i, material)?);
+
+            if i % 1000 == 0 { // Check for a Ctrl+C interrupt, catched by Python.
+                ctrlc_catched()?;
+            }
+        }
+        vectorized_result(py, iterations, values)
     }
 }
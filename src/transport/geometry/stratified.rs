@@ -1,9 +1,10 @@
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use crate::numerics::{Float, Float3};
 use crate::numerics::grids::{Grid, GridCoordinate, LinearGrid};
 use crate::numerics::interpolate::BilinearInterpolator;
 use crate::physics::materials::MaterialDefinition;
 use crate::transport::density::DensityModel;
+use memmap2::Mmap;
 use std::rc::Rc;
 use super::{GeometryDefinition, GeometrySector, GeometryTracer};
 
@@ -15,15 +16,192 @@ use super::{GeometryDefinition, GeometrySector, GeometryTracer};
 pub struct TopographyMap {
     x: LinearGrid,
     y: LinearGrid,
-    pub(crate) z: BilinearInterpolator,
+    shape: (usize, usize), // (ny, nx).
+    bounds: (Float, Float, Float, Float), // (xmin, xmax, ymin, ymax).
+    pub(crate) storage: TopographyStorage,
 }
 
 impl TopographyMap {
     pub fn new(xmin: Float, xmax: Float, nx: usize, ymin: Float, ymax: Float, ny: usize) -> Self {
         let x = LinearGrid::new(xmin, xmax, nx);
         let y = LinearGrid::new(ymin, ymax, ny);
-        let z = BilinearInterpolator::new(ny, nx);
-        Self { x, y, z }
+        let storage = TopographyStorage::Dense(BilinearInterpolator::new(ny, nx));
+        let bounds = (xmin, xmax, ymin, ymax);
+        Self { x, y, shape: (ny, nx), bounds, storage }
+    }
+
+    /// Opens a memory-mapped, out-of-core elevation grid, read from a raw binary `z` file.
+    ///
+    /// The file is expected to hold `nx * ny` elevation values of the given `dtype`, in row-major
+    /// (C) order, without any header.
+    pub fn open(
+        path: &str,
+        xmin: Float,
+        xmax: Float,
+        nx: usize,
+        ymin: Float,
+        ymax: Float,
+        ny: usize,
+        dtype: ElevationDtype,
+    ) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        let expected = nx * ny * dtype.size();
+        if mmap.len() != expected {
+            bail!(
+                "bad file size for '{}' (expected {} bytes, found {})",
+                path,
+                expected,
+                mmap.len(),
+            )
+        }
+        let x = LinearGrid::new(xmin, xmax, nx);
+        let y = LinearGrid::new(ymin, ymax, ny);
+        let storage = TopographyStorage::Mapped(MappedElevation { mmap, dtype });
+        let bounds = (xmin, xmax, ymin, ymax);
+        Ok(Self { x, y, shape: (ny, nx), bounds, storage })
+    }
+
+    /// Reads an ESRI ASCII grid (`.asc`) DEM file.
+    ///
+    /// Grid nodes are placed at cell centers, following the file's `xllcorner`/`yllcorner` (or
+    /// `xllcenter`/`yllcenter`) and `cellsize` header fields. The `NODATA_value` sentinel, if
+    /// present, is mapped to `NaN`.
+    pub fn from_asc(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut lines = text.lines().peekable();
+
+        // Header lines start with a keyword; the data block starts at the first numeric token.
+        let mut header = std::collections::HashMap::new();
+        while let Some(&line) = lines.peek() {
+            let mut parts = line.split_whitespace();
+            let key = match parts.next() {
+                Some(key) if key.parse::<Float>().is_err() => key.to_lowercase(),
+                _ => break,
+            };
+            let value = parts.next()
+                .ok_or_else(|| anyhow!("bad ESRI ASCII grid header in '{}'", path))?;
+            header.insert(key, value.to_string());
+            lines.next();
+        }
+
+        let get = |key: &str| -> Result<&String> {
+            header.get(key).ok_or_else(|| anyhow!(
+                "missing '{}' in ESRI ASCII grid header of '{}'", key, path
+            ))
+        };
+        let ncols: usize = get("ncols")?.parse()?;
+        let nrows: usize = get("nrows")?.parse()?;
+        let cellsize: Float = get("cellsize")?.parse()?;
+        let nodata: Float = header.get("nodata_value")
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or(-9999.0);
+
+        let (xmin, ymin) = match (header.get("xllcenter"), header.get("yllcenter")) {
+            (Some(x), Some(y)) => (x.parse::<Float>()?, y.parse::<Float>()?),
+            _ => (
+                get("xllcorner")?.parse::<Float>()? + 0.5 * cellsize,
+                get("yllcorner")?.parse::<Float>()? + 0.5 * cellsize,
+            ),
+        };
+        let xmax = xmin + ((ncols - 1) as Float) * cellsize;
+        let ymax = ymin + ((nrows - 1) as Float) * cellsize;
+
+        let mut values = lines.flat_map(|line| line.split_whitespace());
+        let mut map = Self::new(xmin, xmax, ncols, ymin, ymax, nrows);
+        {
+            let dense = map.storage.as_dense_mut().unwrap();
+            for r in 0..nrows {
+                for c in 0..ncols {
+                    let token = values.next().ok_or_else(|| anyhow!(
+                        "truncated ESRI ASCII grid data in '{}'", path
+                    ))?;
+                    let value: Float = token.parse()?;
+                    let value = if value == nodata { Float::NAN } else { value };
+                    // File rows run north (top) to south (bottom); grid rows run south to north.
+                    dense[(nrows - 1 - r, c)] = value;
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Reads a single band of a GeoTIFF DEM file.
+    ///
+    /// The grid's geotransform (origin and cell size) is taken from the file's
+    /// `ModelPixelScaleTag`/`ModelTiepointTag` GeoTIFF tags; the `GDAL_NODATA` tag, if present, is
+    /// mapped to `NaN`. `band` is 1-indexed, as is customary for raster bands.
+    pub fn from_geotiff(path: &str, band: usize) -> Result<Self> {
+        use tiff::decoder::{Decoder, DecodingResult};
+        use tiff::tags::Tag;
+
+        let file = std::fs::File::open(path)?;
+        let mut decoder = Decoder::new(file)?;
+        let (ncols, nrows) = decoder.dimensions()?;
+        let (ncols, nrows) = (ncols as usize, nrows as usize);
+
+        let pixel_scale = decoder.get_tag_f64_vec(Tag::Unknown(33550))?;
+        let tie_point = decoder.get_tag_f64_vec(Tag::Unknown(33922))?;
+        if pixel_scale.len() < 2 || tie_point.len() < 6 {
+            bail!("missing geotransform tags in GeoTIFF '{}'", path)
+        }
+        let (dx, dy) = (pixel_scale[0], pixel_scale[1]);
+        let (ox, oy) = (tie_point[3], tie_point[4]); // Raster origin (upper-left corner).
+
+        let nodata: Float = decoder
+            .get_tag_ascii_string(Tag::Unknown(42113))
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(Float::NAN);
+
+        // Grid nodes at cell centers; rows run north (top) to south in image space.
+        let xmin = (ox + 0.5 * dx) as Float;
+        let xmax = xmin + ((ncols - 1) as Float) * (dx as Float);
+        let ymax = (oy - 0.5 * dy) as Float;
+        let ymin = ymax - ((nrows - 1) as Float) * (dy as Float);
+
+        let image: Vec<Float> = match decoder.read_image()? {
+            DecodingResult::F32(data) => data.into_iter().map(|v| v as Float).collect(),
+            DecodingResult::F64(data) => data.into_iter().map(|v| v as Float).collect(),
+            DecodingResult::I16(data) => data.into_iter().map(|v| v as Float).collect(),
+            DecodingResult::U16(data) => data.into_iter().map(|v| v as Float).collect(),
+            DecodingResult::U8(data) => data.into_iter().map(|v| v as Float).collect(),
+            _ => bail!("unsupported GeoTIFF sample format in '{}'", path),
+        };
+        let nbands = (image.len() / (ncols * nrows)).max(1);
+        if band == 0 || band > nbands {
+            bail!(
+                "bad band for '{}' (expected a value in [1, {}], found {})",
+                path,
+                nbands,
+                band,
+            )
+        }
+
+        let mut map = Self::new(xmin, xmax, ncols, ymin, ymax, nrows);
+        {
+            let dense = map.storage.as_dense_mut().unwrap();
+            for r in 0..nrows {
+                for c in 0..ncols {
+                    let k = (r * ncols + c) * nbands + (band - 1);
+                    let value = image[k];
+                    let value = if value == nodata { Float::NAN } else { value };
+                    dense[(nrows - 1 - r, c)] = value;
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Returns the map's `(xmin, xmax, ymin, ymax)` extent.
+    pub fn bounds(&self) -> (Float, Float, Float, Float) {
+        self.bounds
+    }
+
+    /// Returns the map's `(ny, nx)` grid shape.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
     }
 
     pub fn z(&self, x: Float, y: Float) -> Option<Float> {
@@ -35,9 +213,292 @@ impl TopographyMap {
             GridCoordinate::Inside(j, hj) => (j, hj),
             _ => return None,
         };
-        let zij = self.z.interpolate_raw(i, hi, j, hj);
+        let zij = match &self.storage {
+            TopographyStorage::Dense(interpolator) => interpolator.interpolate_raw(i, hi, j, hj),
+            TopographyStorage::Mapped(mapped) => mapped.interpolate_raw(self.shape, i, hi, j, hj),
+        };
         Some(zij)
     }
+
+    #[inline]
+    fn dx(&self) -> Float {
+        let (_, nx) = self.shape;
+        let (xmin, xmax, _, _) = self.bounds;
+        (xmax - xmin) / ((nx - 1) as Float)
+    }
+
+    #[inline]
+    fn dy(&self) -> Float {
+        let (ny, _) = self.shape;
+        let (_, _, ymin, ymax) = self.bounds;
+        (ymax - ymin) / ((ny - 1) as Float)
+    }
+
+    #[inline]
+    fn corner(&self, i: usize, j: usize) -> Float {
+        match &self.storage {
+            TopographyStorage::Dense(interpolator) => interpolator[(i, j)],
+            TopographyStorage::Mapped(mapped) => mapped.get(self.shape, i, j),
+        }
+    }
+
+    /// Finds the first crossing of the ray (`origin`, `direction`) with this map's bilinear
+    /// elevation surface, shifted by `offset`, within the parametric range `(0, t_max]`.
+    ///
+    /// This walks the grid cells actually traversed by the ray, using a 2D DDA (digital
+    /// differential analyzer): at each step, `t_max_x`/`t_max_y` track the parametric distance to
+    /// the next vertical/horizontal cell boundary and the smallest one is advanced, bounding the
+    /// per-step work to the cells the ray crosses. Within each visited cell, the crossing with the
+    /// bilinear patch formed by the four corner elevations is solved for exactly (see
+    /// `cell_crossing`).
+    pub(crate) fn crossing(
+        &self,
+        origin: Float3,
+        direction: Float3,
+        t_max: Float,
+        offset: Float,
+    ) -> Option<Float> {
+        let (ny, nx) = self.shape;
+        if nx < 2 || ny < 2 || t_max <= 0.0 {
+            return None;
+        }
+        let (xmin, _, ymin, _) = self.bounds;
+        let dx = self.dx();
+        let dy = self.dy();
+
+        // Locate the starting cell. Rays starting outside of the map's horizontal domain are not
+        // handled here (the caller falls back to other topography data in that case).
+        let fx = (origin.0 - xmin) / dx;
+        let fy = (origin.1 - ymin) / dy;
+        if fx < 0.0 || fx > (nx - 1) as Float || fy < 0.0 || fy > (ny - 1) as Float {
+            return None;
+        }
+        let mut j = (fx as isize).clamp(0, nx as isize - 2);
+        let mut i = (fy as isize).clamp(0, ny as isize - 2);
+
+        // Per-axis DDA parameters: step direction, parametric distance to the next boundary, and
+        // the parametric width of a cell. A zero component leaves `t_max_*` at infinity, i.e. the
+        // ray never crosses that axis' cell boundaries.
+        let (step_j, mut t_max_x, t_delta_x) = if direction.0 > 0.0 {
+            let next = xmin + ((j + 1) as Float) * dx;
+            (1_isize, (next - origin.0) / direction.0, dx / direction.0)
+        } else if direction.0 < 0.0 {
+            let next = xmin + (j as Float) * dx;
+            (-1_isize, (next - origin.0) / direction.0, -dx / direction.0)
+        } else {
+            (0_isize, Float::INFINITY, Float::INFINITY)
+        };
+        let (step_i, mut t_max_y, t_delta_y) = if direction.1 > 0.0 {
+            let next = ymin + ((i + 1) as Float) * dy;
+            (1_isize, (next - origin.1) / direction.1, dy / direction.1)
+        } else if direction.1 < 0.0 {
+            let next = ymin + (i as Float) * dy;
+            (-1_isize, (next - origin.1) / direction.1, -dy / direction.1)
+        } else {
+            (0_isize, Float::INFINITY, Float::INFINITY)
+        };
+
+        let mut t_enter = 0.0;
+        loop {
+            let t_next = t_max_x.min(t_max_y);
+            let t_exit = t_next.min(t_max);
+            if t_exit > t_enter {
+                if let Some(t) = self.cell_crossing(
+                    origin, direction, offset, i as usize, j as usize, t_enter, t_exit
+                ) {
+                    return Some(t);
+                }
+            }
+            if t_next >= t_max {
+                return None; // The ray left the step length without crossing the surface.
+            }
+            t_enter = t_next;
+            // Advance into the adjacent cell along whichever axis is closer.
+            if t_max_x < t_max_y {
+                j += step_j;
+                if j < 0 || j > nx as isize - 2 {
+                    return None; // The ray left the map's domain along x.
+                }
+                t_max_x += t_delta_x;
+            } else {
+                i += step_i;
+                if i < 0 || i > ny as isize - 2 {
+                    return None; // The ray left the map's domain along y.
+                }
+                t_max_y += t_delta_y;
+            }
+        }
+    }
+
+    /// Solves for the ray/bilinear-patch crossing within cell (`i`, `j`), over `t` in
+    /// `(t_enter, t_exit]`.
+    ///
+    /// Substituting the parametric `x(t)`, `y(t)` into the bilinear form reduces the crossing
+    /// condition `ray_z(t) - bilinear(x(t), y(t)) == 0` to a quadratic in `t`; the smallest root
+    /// inside the cell's `t`-interval is returned. A monotone patch degenerates this quadratic to
+    /// a linear equation, which is handled separately.
+    fn cell_crossing(
+        &self,
+        origin: Float3,
+        direction: Float3,
+        offset: Float,
+        i: usize,
+        j: usize,
+        t_enter: Float,
+        t_exit: Float,
+    ) -> Option<Float> {
+        let dx = self.dx();
+        let dy = self.dy();
+        let (xmin, _, ymin, _) = self.bounds;
+
+        let z00 = self.corner(i, j) + offset;
+        let z01 = self.corner(i, j + 1) + offset;
+        let z10 = self.corner(i + 1, j) + offset;
+        let z11 = self.corner(i + 1, j + 1) + offset;
+
+        // Local (u, v) cell coordinates, linear in t: u along x (columns), v along y (rows).
+        let x0 = xmin + (j as Float) * dx;
+        let y0 = ymin + (i as Float) * dy;
+        let u0 = (origin.0 - x0) / dx;
+        let v0 = (origin.1 - y0) / dy;
+        let du = direction.0 / dx;
+        let dv = direction.1 / dy;
+
+        // bilinear(u, v) = z00 + a*u + b*v + c*u*v.
+        let a = z01 - z00;
+        let b = z10 - z00;
+        let c = z11 - z10 - z01 + z00;
+
+        // ray_z(t) - bilinear(u(t), v(t)) = coef_a*t^2 + coef_b*t + coef_c.
+        let coef_a = -c * du * dv;
+        let coef_b = direction.2 - a * du - b * dv - c * (u0 * dv + v0 * du);
+        let coef_c = origin.2 - z00 - a * u0 - b * v0 - c * u0 * v0;
+
+        let roots: Vec<Float> = if coef_a.abs() < 1E-12 {
+            if coef_b == 0.0 {
+                return None;
+            }
+            vec![-coef_c / coef_b]
+        } else {
+            let delta = coef_b * coef_b - 4.0 * coef_a * coef_c;
+            if delta < 0.0 {
+                return None;
+            }
+            let sq = delta.sqrt();
+            vec![(-coef_b - sq) / (2.0 * coef_a), (-coef_b + sq) / (2.0 * coef_a)]
+        };
+
+        roots
+            .into_iter()
+            .filter(|t| *t > t_enter && *t <= t_exit)
+            .fold(None, |best, t| match best {
+                None => Some(t),
+                Some(b) => Some(if t < b { t } else { b }),
+            })
+    }
+}
+
+
+// ===============================================================================================
+// Storage backend for a topography map's elevation grid: either owned in-RAM, or memory-mapped.
+// ===============================================================================================
+
+pub enum TopographyStorage {
+    Dense(BilinearInterpolator),
+    Mapped(MappedElevation),
+}
+
+impl TopographyStorage {
+    /// Returns the owned elevation data, if any.
+    pub fn as_dense(&self) -> Option<&BilinearInterpolator> {
+        match self {
+            Self::Dense(interpolator) => Some(interpolator),
+            Self::Mapped(_) => None,
+        }
+    }
+
+    /// Returns a mutable view of the owned elevation data, if any.
+    pub fn as_dense_mut(&mut self) -> Option<&mut BilinearInterpolator> {
+        match self {
+            Self::Dense(interpolator) => Some(interpolator),
+            Self::Mapped(_) => None,
+        }
+    }
+}
+
+/// Memory-mapped, read-only elevation grid.
+pub struct MappedElevation {
+    mmap: Mmap,
+    dtype: ElevationDtype,
+}
+
+impl MappedElevation {
+    fn get(&self, shape: (usize, usize), i: usize, j: usize) -> Float {
+        let (_, nx) = shape;
+        let index = i * nx + j;
+        self.dtype.read(&self.mmap, index)
+    }
+
+    fn interpolate_raw(
+        &self,
+        shape: (usize, usize),
+        i: usize,
+        hi: Float,
+        j: usize,
+        hj: Float,
+    ) -> Float {
+        let z00 = self.get(shape, i, j);
+        let z01 = self.get(shape, i, j + 1);
+        let z10 = self.get(shape, i + 1, j);
+        let z11 = self.get(shape, i + 1, j + 1);
+        let z0 = z00 * (1.0 - hj) + z01 * hj;
+        let z1 = z10 * (1.0 - hj) + z11 * hj;
+        z0 * (1.0 - hi) + z1 * hi
+    }
+}
+
+/// On-disk scalar type of a memory-mapped elevation grid.
+#[derive(Clone, Copy)]
+pub enum ElevationDtype {
+    F32,
+    F64,
+}
+
+impl ElevationDtype {
+    fn size(&self) -> usize {
+        match self {
+            Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    fn read(&self, data: &[u8], index: usize) -> Float {
+        match self {
+            Self::F32 => {
+                let bytes: [u8; 4] = data[(4 * index)..(4 * index + 4)].try_into().unwrap();
+                f32::from_le_bytes(bytes) as Float
+            },
+            Self::F64 => {
+                let bytes: [u8; 8] = data[(8 * index)..(8 * index + 8)].try_into().unwrap();
+                f64::from_le_bytes(bytes) as Float
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for ElevationDtype {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "f4" | "f32" => Ok(Self::F32),
+            "f8" | "f64" => Ok(Self::F64),
+            _ => bail!(
+                "bad dtype (expected 'f32' or 'f64', found '{}')",
+                value,
+            ),
+        }
+    }
 }
 
 
@@ -305,6 +766,47 @@ impl ResolvedData {
         }
         None
     }
+
+    fn crossing(
+        &self,
+        cache: &[CachedValue],
+        origin: Float3,
+        direction: Float3,
+        t_max: Float,
+    ) -> Option<Float> {
+        match self {
+            Self::Constant(value) => {
+                if direction.2 == 0.0 {
+                    None
+                } else {
+                    let t = (*value - origin.2) / direction.2;
+                    if t > 0.0 && t <= t_max { Some(t) } else { None }
+                }
+            },
+            Self::Map(index) => cache[*index].map.crossing(origin, direction, t_max, 0.0),
+            Self::Offset(index, value) => {
+                cache[*index].map.crossing(origin, direction, t_max, *value)
+            },
+        }
+    }
+
+    fn interface_crossing(
+        interface: &[Self],
+        cache: &mut [CachedValue],
+        origin: Float3,
+        direction: Float3,
+        t_max: Float,
+    ) -> Option<Float> {
+        // First-match-wins, mirroring `interface_z`: the first entry whose domain covers the
+        // ray's (x, y) origin fully overrides any lower-priority fallback there, whether or not
+        // it actually registers a crossing.
+        for data in interface.iter() {
+            if data.compute_z(cache, origin.0, origin.1).is_some() {
+                return data.crossing(cache, origin, direction, t_max);
+            }
+        }
+        None
+    }
 }
 
 impl<'a> StratifiedTracer<'a> {
@@ -407,7 +909,55 @@ impl<'a> GeometryTracer<'a, StratifiedGeometry> for StratifiedTracer<'a> {
     }
 
     fn trace(&mut self, physical_length: Float) -> Result<Float> {
-        // XXX HERE I AM. Implement this.
+        let n = self.definition.interfaces.len();
+        let sector = match self.current_sector {
+            None => {
+                // Not inside any sector: advance blindly, the geometry handles re-entry on the
+                // next `reset`.
+                self.length = physical_length;
+                self.next_sector = None;
+                return Ok(self.length);
+            },
+            Some(sector) => sector,
+        };
+
+        let mut length = physical_length;
+        let mut next_sector = Some(sector); // Unchanged, unless an interface is crossed first.
+
+        // Lower interface: crossing it downward exits into the layer below, or out of the
+        // geometry if this is the bottom-most sector.
+        let lower = ResolvedData::interface_crossing(
+            &self.definition.interfaces[sector],
+            &mut self.cache,
+            self.position,
+            self.direction,
+            length,
+        );
+        if let Some(t) = lower {
+            if t < length {
+                length = t;
+                next_sector = if sector == 0 { None } else { Some(sector - 1) };
+            }
+        }
+
+        // Upper interface: crossing it upward enters the layer above, or exits the geometry if
+        // this is the top-most sector.
+        let upper = ResolvedData::interface_crossing(
+            &self.definition.interfaces[sector + 1],
+            &mut self.cache,
+            self.position,
+            self.direction,
+            length,
+        );
+        if let Some(t) = upper {
+            if t < length {
+                length = t;
+                next_sector = if sector + 1 == n - 1 { None } else { Some(sector + 1) };
+            }
+        }
+
+        self.length = length;
+        self.next_sector = next_sector;
         Ok(self.length)
     }
 